@@ -1,30 +1,99 @@
 use std::collections::{HashMap, HashSet};
 use std::fs::{self, OpenOptions};
 use std::io::{self, Write, Read};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Clone)]
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, Utc};
+use clap::{Parser, Subcommand};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 enum TaskStatus {
     Pending,
     Completed,
     Deleted,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+enum Priority {
+    #[default]
+    Low,
+    Medium,
+    High,
+}
+
+impl Priority {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Priority::Low => "Low",
+            Priority::Medium => "Medium",
+            Priority::High => "High",
+        }
+    }
+
+    fn from_str(s: &str) -> Priority {
+        match s {
+            "Medium" => Priority::Medium,
+            "High" => Priority::High,
+            _ => Priority::Low,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TimeEntry {
+    date: String,
+    hours: u32,
+    minutes: u32,
+}
+
+impl TimeEntry {
+    fn new(date: String, hours: u32, minutes: u32) -> Self {
+        TimeEntry {
+            date,
+            hours: hours + minutes / 60,
+            minutes: minutes % 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct Task {
     id: u32,
     title: String,
     description: String,
     status: TaskStatus,
+    due: Option<DateTime<Utc>>,
+    priority: Priority,
+    tags: HashSet<String>,
+    dependencies: HashSet<u32>,
+    time_entries: Vec<TimeEntry>,
+    #[serde(default)]
+    order: u32,
 }
 
 impl Task {
-    fn new(id: u32, title: String, description: String) -> Self {
+    fn new(
+        id: u32,
+        title: String,
+        description: String,
+        due: Option<DateTime<Utc>>,
+        priority: Priority,
+        tags: HashSet<String>,
+        order: u32,
+    ) -> Self {
         Task {
             id,
             title,
             description,
             status: TaskStatus::Pending,
+            due,
+            priority,
+            tags,
+            dependencies: HashSet::new(),
+            time_entries: Vec::new(),
+            order,
         }
     }
 
@@ -35,43 +104,193 @@ impl Task {
     fn mark_deleted(&mut self) {
         self.status = TaskStatus::Deleted;
     }
+
+    fn is_overdue(&self) -> bool {
+        match &self.due {
+            Some(due) => !matches!(self.status, TaskStatus::Completed | TaskStatus::Deleted) && *due < Utc::now(),
+            None => false,
+        }
+    }
+
+    fn log_time(&mut self, date: String, hours: u32, minutes: u32) {
+        self.time_entries.push(TimeEntry::new(date, hours, minutes));
+    }
+
+    fn total_time(&self) -> (u32, u32) {
+        let total_minutes: u32 = self.time_entries.iter().map(|e| e.hours * 60 + e.minutes).sum();
+        (total_minutes / 60, total_minutes % 60)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct TaskManagerData {
+    tasks: HashMap<u32, Task>,
+    next_id: u32,
 }
 
 struct TaskManager {
     tasks: HashMap<u32, Task>,
     next_id: u32,
-    filename: String,
+    path: PathBuf,
 }
 
 impl TaskManager {
-    fn new(filename: String) -> Self {
+    fn new() -> Self {
         let mut manager = TaskManager {
             tasks: HashMap::new(),
             next_id: 1,
-            filename,
+            path: Self::data_file_path(),
         };
         manager.load_from_file();
         manager
     }
 
-    fn add_task(&mut self, title: String, description: String) {
-        let task = Task::new(self.next_id, title, description);
+    fn data_file_path() -> PathBuf {
+        ProjectDirs::from("", "", "a-bit-rusty")
+            .map(|dirs| dirs.data_dir().join("tasks.json"))
+            .unwrap_or_else(|| PathBuf::from("tasks.json"))
+    }
+
+    fn add_task(
+        &mut self,
+        title: String,
+        description: String,
+        due: Option<DateTime<Utc>>,
+        priority: Priority,
+        tags: HashSet<String>,
+    ) {
+        let order = self.next_order();
+        let task = Task::new(self.next_id, title, description, due, priority, tags, order);
         self.tasks.insert(self.next_id, task);
         self.next_id += 1;
         self.save_to_file();
     }
 
-    fn list_tasks(&self) {
-        for task in self.tasks.values() {
-            match task.status {
-                TaskStatus::Pending => println!("ID: {}, Title: {}, Status: Pending", task.id, task.title),
-                TaskStatus::Completed => println!("ID: {}, Title: {}, Status: Completed", task.id, task.title),
-                TaskStatus::Deleted => println!("ID: {}, Title: {}, Status: Deleted", task.id, task.title),
+    fn ordered_tasks(&self) -> Vec<&Task> {
+        let mut tasks: Vec<&Task> = self.tasks.values().collect();
+        tasks.sort_by_key(|task| task.order);
+        tasks
+    }
+
+    fn next_order(&self) -> u32 {
+        self.tasks.values().map(|task| task.order).max().map_or(0, |max| max + 1)
+    }
+
+    // Tasks saved before the `order` field existed all deserialize with `order: 0`
+    // (see `#[serde(default)]` on `Task::order`). Left alone, `ordered_tasks()` would
+    // break that tie using `HashMap` iteration order, which is randomized per process —
+    // reintroducing the nondeterminism this field was added to remove. Renumber by id
+    // once, on load, and persist so the ambiguity doesn't resurface on the next run.
+    fn renumber_orders_if_duplicated(&mut self) {
+        let zero_order_count = self.tasks.values().filter(|task| task.order == 0).count();
+        if zero_order_count <= 1 {
+            return;
+        }
+
+        let mut ids: Vec<u32> = self.tasks.keys().copied().collect();
+        ids.sort();
+        for (order, id) in ids.into_iter().enumerate() {
+            if let Some(task) = self.tasks.get_mut(&id) {
+                task.order = order as u32;
             }
         }
+        self.save_to_file();
+    }
+
+    fn move_before(&mut self, id: u32, target: u32) {
+        self.reorder(id, target, true);
+    }
+
+    fn move_after(&mut self, id: u32, target: u32) {
+        self.reorder(id, target, false);
+    }
+
+    fn reorder(&mut self, id: u32, target: u32, before: bool) {
+        if id == target {
+            println!("A task cannot be moved relative to itself.");
+            return;
+        }
+        if !self.tasks.contains_key(&id) || !self.tasks.contains_key(&target) {
+            println!("Task with ID {} or {} not found.", id, target);
+            return;
+        }
+
+        let mut ids: Vec<u32> = self.ordered_tasks().iter().map(|task| task.id).collect();
+        ids.retain(|&task_id| task_id != id);
+        let target_pos = ids.iter().position(|&task_id| task_id == target).unwrap();
+        let insert_pos = if before { target_pos } else { target_pos + 1 };
+        ids.insert(insert_pos, id);
+
+        for (order, task_id) in ids.into_iter().enumerate() {
+            if let Some(task) = self.tasks.get_mut(&task_id) {
+                task.order = order as u32;
+            }
+        }
+        self.save_to_file();
+    }
+
+    fn list_tasks(&self) {
+        for task in self.ordered_tasks() {
+            println!("{}", self.format_task(task));
+        }
+    }
+
+    fn list_tasks_by_tag(&self, tag: &str) {
+        for task in self.tasks_with_tag(tag) {
+            println!("{}", self.format_task(task));
+        }
+    }
+
+    fn tasks_with_tag(&self, tag: &str) -> Vec<&Task> {
+        self.ordered_tasks().into_iter().filter(|task| task.tags.contains(tag)).collect()
+    }
+
+    fn format_task(&self, task: &Task) -> String {
+        let status_str = match task.status {
+            TaskStatus::Pending => "Pending",
+            TaskStatus::Completed => "Completed",
+            TaskStatus::Deleted => "Deleted",
+        };
+        let due_str = match &task.due {
+            Some(due) => format!(", Due: {}", due.with_timezone(&Local).format("%Y-%m-%d %H:%M")),
+            None => String::new(),
+        };
+        let overdue_str = if task.is_overdue() { " [OVERDUE]" } else { "" };
+        let tags_str = if task.tags.is_empty() {
+            String::new()
+        } else {
+            let mut tags: Vec<&str> = task.tags.iter().map(|t| t.as_str()).collect();
+            tags.sort();
+            format!(", Tags: {}", tags.join(", "))
+        };
+        let blocked_str = if self.pending_dependencies(task.id).is_empty() {
+            ""
+        } else {
+            " [BLOCKED]"
+        };
+        let (total_hours, total_minutes) = task.total_time();
+        let time_str = if task.time_entries.is_empty() {
+            String::new()
+        } else {
+            format!(", Time logged: {}h{}m", total_hours, total_minutes)
+        };
+        format!(
+            "ID: {}, Title: {}, Status: {}, Priority: {}{}{}{}{}{}",
+            task.id, task.title, status_str, task.priority.as_str(), due_str, tags_str, time_str, overdue_str, blocked_str
+        )
     }
 
     fn mark_completed(&mut self, task_id: u32) {
+        let pending_deps = self.pending_dependencies(task_id);
+        if !pending_deps.is_empty() {
+            println!(
+                "Task {} is blocked by incomplete dependencies: {}",
+                task_id,
+                pending_deps.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ")
+            );
+            return;
+        }
+
         if let Some(task) = self.tasks.get_mut(&task_id) {
             task.mark_completed();
             self.save_to_file();
@@ -80,6 +299,89 @@ impl TaskManager {
         }
     }
 
+    fn pending_dependencies(&self, task_id: u32) -> Vec<u32> {
+        match self.tasks.get(&task_id) {
+            Some(task) => {
+                let mut pending: Vec<u32> = task
+                    .dependencies
+                    .iter()
+                    .filter(|dep_id| {
+                        !matches!(self.tasks.get(dep_id), Some(dep) if matches!(dep.status, TaskStatus::Completed))
+                    })
+                    .copied()
+                    .collect();
+                pending.sort();
+                pending
+            }
+            None => Vec::new(),
+        }
+    }
+
+    fn log_time(&mut self, task_id: u32, hours: u32, minutes: u32) {
+        if let Some(task) = self.tasks.get_mut(&task_id) {
+            let date = Local::now().format("%Y-%m-%d").to_string();
+            task.log_time(date, hours, minutes);
+            self.save_to_file();
+        } else {
+            println!("Task with ID {} not found.", task_id);
+        }
+    }
+
+    fn add_dependency(&mut self, task_id: u32, depends_on: u32) {
+        if !self.tasks.contains_key(&task_id) || !self.tasks.contains_key(&depends_on) {
+            println!("Task with ID {} or {} not found.", task_id, depends_on);
+            return;
+        }
+        if task_id == depends_on {
+            println!("A task cannot depend on itself.");
+            return;
+        }
+        if self.can_reach(depends_on, task_id) {
+            println!(
+                "Cannot add dependency: task {} already (transitively) depends on task {}, this would create a cycle.",
+                depends_on, task_id
+            );
+            return;
+        }
+
+        if let Some(task) = self.tasks.get_mut(&task_id) {
+            task.dependencies.insert(depends_on);
+        }
+        self.save_to_file();
+    }
+
+    fn remove_dependency(&mut self, task_id: u32, depends_on: u32) {
+        match self.tasks.get_mut(&task_id) {
+            Some(task) => {
+                if task.dependencies.remove(&depends_on) {
+                    self.save_to_file();
+                } else {
+                    println!("Task {} does not depend on task {}.", task_id, depends_on);
+                }
+            }
+            None => println!("Task with ID {} not found.", task_id),
+        }
+    }
+
+    fn can_reach(&self, from: u32, to: u32) -> bool {
+        let mut visited = HashSet::new();
+        let mut stack = vec![from];
+        while let Some(current) = stack.pop() {
+            if current == to {
+                return true;
+            }
+            if !visited.insert(current) {
+                continue;
+            }
+            if let Some(task) = self.tasks.get(&current) {
+                for &dep in &task.dependencies {
+                    stack.push(dep);
+                }
+            }
+        }
+        false
+    }
+
     fn mark_deleted(&mut self, task_id: u32) {
         if let Some(task) = self.tasks.get_mut(&task_id) {
             task.mark_deleted();
@@ -90,29 +392,87 @@ impl TaskManager {
     }
 
     fn delete_task(&mut self, task_id: u32) {
-        if self.tasks.remove(&task_id).is_some() {
-            self.save_to_file();
-        } else {
+        if !self.tasks.contains_key(&task_id) {
             println!("Task with ID {} not found.", task_id);
+            return;
         }
+
+        let dependents = self.dependents_of(task_id);
+        if !dependents.is_empty() {
+            println!(
+                "Cannot delete task {}: it is still a dependency of task(s) {}. Remove those dependencies first.",
+                task_id,
+                dependents.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ")
+            );
+            return;
+        }
+
+        self.tasks.remove(&task_id);
+        self.save_to_file();
+    }
+
+    // Tasks that list `task_id` in their own `dependencies`, i.e. would be left
+    // permanently blocked (see `pending_dependencies`) if `task_id` disappeared.
+    fn dependents_of(&self, task_id: u32) -> Vec<u32> {
+        let mut dependents: Vec<u32> = self
+            .tasks
+            .values()
+            .filter(|task| task.dependencies.contains(&task_id))
+            .map(|task| task.id)
+            .collect();
+        dependents.sort();
+        dependents
     }
 
     fn load_from_file(&mut self) {
-        if Path::new(&self.filename).exists() {
-            let mut file = match OpenOptions::new().read(true).open(&self.filename) {
+        if self.path.exists() {
+            let mut file = match OpenOptions::new().read(true).open(&self.path) {
                 Ok(file) => file,
                 Err(_) => return,
             };
 
             let mut contents = String::new();
-            if let Err(_) = file.read_to_string(&mut contents) {
+            if file.read_to_string(&mut contents).is_err() {
                 return;
             }
 
+            if let Ok(data) = serde_json::from_str::<TaskManagerData>(&contents) {
+                self.tasks = data.tasks;
+                self.next_id = data.next_id;
+                self.renumber_orders_if_duplicated();
+            }
+            return;
+        }
+
+        // No JSON store yet: migrate a legacy pipe-delimited `tasks.txt`, if one exists.
+        let legacy_path = Path::new("tasks.txt");
+        if legacy_path.exists() {
+            self.load_legacy_file(legacy_path);
+            self.save_to_file();
+            // Rename rather than delete: if tasks.json is later removed to reset state,
+            // a lingering tasks.txt would otherwise silently resurrect the old data.
+            if let Err(e) = fs::rename(legacy_path, "tasks.txt.migrated") {
+                println!("Warning: migrated tasks but could not rename {}: {}", legacy_path.display(), e);
+            }
+        }
+    }
+
+    fn load_legacy_file(&mut self, legacy_path: &Path) {
+        let mut file = match OpenOptions::new().read(true).open(legacy_path) {
+            Ok(file) => file,
+            Err(_) => return,
+        };
+
+        let mut contents = String::new();
+        if file.read_to_string(&mut contents).is_err() {
+            return;
+        }
+
+        {
             let lines = contents.lines();
             for line in lines {
                 let parts: Vec<&str> = line.split('|').collect();
-                if parts.len() == 4 {
+                if parts.len() == 4 || parts.len() == 6 || parts.len() == 7 || parts.len() == 8 || parts.len() == 9 {
                     if let Ok(id) = parts[0].parse::<u32>() {
                         let title = parts[1].to_string();
                         let description = parts[2].to_string();
@@ -122,11 +482,68 @@ impl TaskManager {
                             "Deleted" => TaskStatus::Deleted,
                             _ => continue,
                         };
+                        let (due, priority) = if parts.len() >= 6 {
+                            let due = if parts[4].is_empty() {
+                                None
+                            } else {
+                                DateTime::parse_from_rfc3339(parts[4]).ok().map(|d| d.with_timezone(&Utc))
+                            };
+                            (due, Priority::from_str(parts[5]))
+                        } else {
+                            (None, Priority::Low)
+                        };
+                        let tags = if parts.len() >= 7 {
+                            parts[6]
+                                .split(',')
+                                .map(|t| t.trim())
+                                .filter(|t| !t.is_empty())
+                                .map(|t| t.to_string())
+                                .collect()
+                        } else {
+                            HashSet::new()
+                        };
+                        let dependencies = if parts.len() >= 8 {
+                            parts[7]
+                                .split(',')
+                                .filter(|d| !d.is_empty())
+                                .filter_map(|d| d.parse::<u32>().ok())
+                                .collect()
+                        } else {
+                            HashSet::new()
+                        };
+                        let time_entries = if parts.len() == 9 {
+                            parts[8]
+                                .split(',')
+                                .filter(|e| !e.is_empty())
+                                .filter_map(|e| {
+                                    let fields: Vec<&str> = e.split(':').collect();
+                                    if fields.len() != 3 {
+                                        return None;
+                                    }
+                                    let hours = fields[1].parse::<u32>().ok()?;
+                                    let minutes = fields[2].parse::<u32>().ok()?;
+                                    Some(TimeEntry {
+                                        date: fields[0].to_string(),
+                                        hours,
+                                        minutes,
+                                    })
+                                })
+                                .collect()
+                        } else {
+                            Vec::new()
+                        };
+                        let order = self.tasks.len() as u32;
                         let task = Task {
                             id,
                             title,
                             description,
                             status,
+                            due,
+                            priority,
+                            tags,
+                            dependencies,
+                            time_entries,
+                            order,
                         };
                         self.tasks.insert(id, task);
                         self.next_id = self.next_id.max(id + 1);
@@ -137,7 +554,26 @@ impl TaskManager {
     }
 
     fn save_to_file(&self) {
-        let mut file = match OpenOptions::new().write(true).create(true).truncate(true).open(&self.filename) {
+        if let Some(parent) = self.path.parent() {
+            if let Err(e) = fs::create_dir_all(parent) {
+                println!("Error creating data directory: {}", e);
+                return;
+            }
+        }
+
+        let data = TaskManagerData {
+            tasks: self.tasks.clone(),
+            next_id: self.next_id,
+        };
+        let json = match serde_json::to_string_pretty(&data) {
+            Ok(json) => json,
+            Err(e) => {
+                println!("Error serializing tasks: {}", e);
+                return;
+            }
+        };
+
+        let mut file = match OpenOptions::new().write(true).create(true).truncate(true).open(&self.path) {
             Ok(file) => file,
             Err(e) => {
                 println!("Error opening file: {}", e);
@@ -145,24 +581,141 @@ impl TaskManager {
             }
         };
 
-        for task in self.tasks.values() {
-            let status_str = match task.status {
-                TaskStatus::Pending => "Pending",
-                TaskStatus::Completed => "Completed",
-                TaskStatus::Deleted => "Deleted",
+        if let Err(e) = file.write_all(json.as_bytes()) {
+            println!("Error writing to file: {}", e);
+        }
+    }
+}
+
+#[derive(Parser)]
+#[command(name = "a-bit-rusty", about = "A simple task manager")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Add a new task
+    Add {
+        title: String,
+        #[arg(long, default_value = "")]
+        description: String,
+        /// Due date: RFC3339 (2020-01-21T00:00:00Z), or the shorthand
+        /// 2020-01-21T00:00 / 2020-01-21 (assumed UTC)
+        #[arg(long)]
+        due: Option<String>,
+        /// Priority: Low, Medium, or High
+        #[arg(long, default_value = "Low")]
+        priority: String,
+        /// Comma-separated tags
+        #[arg(long, default_value = "")]
+        tags: String,
+    },
+    /// List all tasks
+    List,
+    /// List tasks carrying the given tag
+    ListByTag { tag: String },
+    /// Mark a task as completed
+    Complete { id: u32 },
+    /// Mark a task as deleted (soft delete)
+    MarkDeleted { id: u32 },
+    /// Remove a task entirely
+    Delete { id: u32 },
+    /// Make one task depend on another
+    Depend { id: u32, depends_on: u32 },
+    /// Remove a dependency between two tasks
+    Undepend { id: u32, depends_on: u32 },
+    /// Log time spent on a task
+    Log { id: u32, hours: u32, minutes: u32 },
+    /// Move a task so it displays immediately before another
+    MoveBefore { id: u32, target: u32 },
+    /// Move a task so it displays immediately after another
+    MoveAfter { id: u32, target: u32 },
+}
+
+// Strict counterpart to `Priority::from_str`: that method defaults unrecognized
+// input to `Low` silently, which is fine for tolerating old data on migration but
+// would quietly corrupt a typo'd `--priority` flag. Returns `None` on anything
+// that isn't exactly "Low", "Medium", or "High" so the caller can warn instead.
+fn parse_priority(input: &str) -> Option<Priority> {
+    match input {
+        "Low" => Some(Priority::Low),
+        "Medium" => Some(Priority::Medium),
+        "High" => Some(Priority::High),
+        _ => None,
+    }
+}
+
+// Accepts full RFC3339 (`2020-01-21T00:00:00Z`) as well as the shorthand forms
+// people actually type: a date-time without an offset (`2020-01-21T00:00`,
+// assumed UTC) or a bare date (`2020-01-21`, assumed UTC midnight).
+fn parse_due_date(input: &str) -> Option<DateTime<Utc>> {
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(input) {
+        return Some(parsed.with_timezone(&Utc));
+    }
+    if let Ok(naive) = NaiveDateTime::parse_from_str(input, "%Y-%m-%dT%H:%M") {
+        return Some(DateTime::from_naive_utc_and_offset(naive, Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return Some(DateTime::from_naive_utc_and_offset(date.and_hms_opt(0, 0, 0).unwrap(), Utc));
+    }
+    None
+}
+
+fn run_command(task_manager: &mut TaskManager, command: Command) {
+    match command {
+        Command::Add { title, description, due, priority, tags } => {
+            let due = due.and_then(|d| match parse_due_date(&d) {
+                Some(parsed) => Some(parsed),
+                None => {
+                    eprintln!("Invalid due date '{}', leaving due date unset.", d);
+                    None
+                }
+            });
+            let priority = if priority.trim().is_empty() {
+                Priority::Low
+            } else {
+                match parse_priority(&priority) {
+                    Some(p) => p,
+                    None => {
+                        eprintln!("Invalid priority '{}', using Low.", priority);
+                        Priority::Low
+                    }
+                }
             };
-            let line = format!("{}|{}|{}|{}\n", task.id, task.title, task.description, status_str);
-            if let Err(e) = file.write_all(line.as_bytes()) {
-                println!("Error writing to file: {}", e);
-                return;
-            }
+            let tags: HashSet<String> = tags
+                .split(',')
+                .map(|t| t.trim())
+                .filter(|t| !t.is_empty())
+                .map(|t| t.to_string())
+                .collect();
+            task_manager.add_task(title, description, due, priority, tags);
         }
+        Command::List => task_manager.list_tasks(),
+        Command::ListByTag { tag } => task_manager.list_tasks_by_tag(&tag),
+        Command::Complete { id } => task_manager.mark_completed(id),
+        Command::MarkDeleted { id } => task_manager.mark_deleted(id),
+        Command::Delete { id } => task_manager.delete_task(id),
+        Command::Depend { id, depends_on } => task_manager.add_dependency(id, depends_on),
+        Command::Undepend { id, depends_on } => task_manager.remove_dependency(id, depends_on),
+        Command::Log { id, hours, minutes } => task_manager.log_time(id, hours, minutes),
+        Command::MoveBefore { id, target } => task_manager.move_before(id, target),
+        Command::MoveAfter { id, target } => task_manager.move_after(id, target),
     }
 }
 
 fn main() {
-    let mut task_manager = TaskManager::new("tasks.txt".to_string());
+    let cli = Cli::parse();
+    let mut task_manager = TaskManager::new();
 
+    match cli.command {
+        Some(command) => run_command(&mut task_manager, command),
+        None => run_interactive(&mut task_manager),
+    }
+}
+
+fn run_interactive(task_manager: &mut TaskManager) {
     loop {
         println!("\nTask Manager");
         println!("1. Add Task");
@@ -170,7 +723,13 @@ fn main() {
         println!("3. Mark Task as Completed");
         println!("4. Mark Task as Deleted");
         println!("5. Delete Task");
-        println!("6. Exit");
+        println!("6. List Tasks by Tag");
+        println!("7. Add Dependency");
+        println!("8. Log Time");
+        println!("9. Move Task Before Another");
+        println!("10. Move Task After Another");
+        println!("11. Remove Dependency");
+        println!("12. Exit");
 
         let mut choice = String::new();
         io::stdin().read_line(&mut choice).unwrap();
@@ -194,7 +753,50 @@ fn main() {
                 io::stdin().read_line(&mut description).unwrap();
                 let description = description.trim().to_string();
 
-                task_manager.add_task(title, description);
+                println!("Enter due date (RFC3339, e.g. 2020-01-21T00:00:00Z, shorthand 2020-01-21T00:00 or 2020-01-21, or leave blank):");
+                let mut due_input = String::new();
+                io::stdin().read_line(&mut due_input).unwrap();
+                let due_input = due_input.trim();
+                let due = if due_input.is_empty() {
+                    None
+                } else {
+                    match parse_due_date(due_input) {
+                        Some(d) => Some(d),
+                        None => {
+                            println!("Invalid date, leaving due date unset.");
+                            None
+                        }
+                    }
+                };
+
+                println!("Enter priority (Low/Medium/High, default Low):");
+                let mut priority_input = String::new();
+                io::stdin().read_line(&mut priority_input).unwrap();
+                let priority_input = priority_input.trim();
+                let priority = if priority_input.is_empty() {
+                    Priority::Low
+                } else {
+                    match parse_priority(priority_input) {
+                        Some(p) => p,
+                        None => {
+                            println!("Invalid priority '{}', using Low.", priority_input);
+                            Priority::Low
+                        }
+                    }
+                };
+
+                println!("Enter tags (comma-separated, or leave blank):");
+                let mut tags_input = String::new();
+                io::stdin().read_line(&mut tags_input).unwrap();
+                let tags: HashSet<String> = tags_input
+                    .trim()
+                    .split(',')
+                    .map(|t| t.trim())
+                    .filter(|t| !t.is_empty())
+                    .map(|t| t.to_string())
+                    .collect();
+
+                task_manager.add_task(title, description, due, priority, tags);
             }
             2 => task_manager.list_tasks(),
             3 => {
@@ -236,8 +838,420 @@ fn main() {
                 };
                 task_manager.delete_task(task_id);
             }
-            6 => break,
+            6 => {
+                println!("Enter tag to filter by:");
+                let mut tag = String::new();
+                io::stdin().read_line(&mut tag).unwrap();
+                let tag = tag.trim();
+                task_manager.list_tasks_by_tag(tag);
+            }
+            7 => {
+                println!("Enter task ID:");
+                let mut task_id = String::new();
+                io::stdin().read_line(&mut task_id).unwrap();
+                let task_id: u32 = match task_id.trim().parse() {
+                    Ok(num) => num,
+                    Err(_) => {
+                        println!("Invalid ID, please try again.");
+                        continue;
+                    }
+                };
+
+                println!("Enter ID of task it depends on:");
+                let mut depends_on = String::new();
+                io::stdin().read_line(&mut depends_on).unwrap();
+                let depends_on: u32 = match depends_on.trim().parse() {
+                    Ok(num) => num,
+                    Err(_) => {
+                        println!("Invalid ID, please try again.");
+                        continue;
+                    }
+                };
+
+                task_manager.add_dependency(task_id, depends_on);
+            }
+            8 => {
+                println!("Enter task ID:");
+                let mut task_id = String::new();
+                io::stdin().read_line(&mut task_id).unwrap();
+                let task_id: u32 = match task_id.trim().parse() {
+                    Ok(num) => num,
+                    Err(_) => {
+                        println!("Invalid ID, please try again.");
+                        continue;
+                    }
+                };
+
+                println!("Enter hours spent:");
+                let mut hours = String::new();
+                io::stdin().read_line(&mut hours).unwrap();
+                let hours: u32 = match hours.trim().parse() {
+                    Ok(num) => num,
+                    Err(_) => {
+                        println!("Invalid number, please try again.");
+                        continue;
+                    }
+                };
+
+                println!("Enter minutes spent:");
+                let mut minutes = String::new();
+                io::stdin().read_line(&mut minutes).unwrap();
+                let minutes: u32 = match minutes.trim().parse() {
+                    Ok(num) => num,
+                    Err(_) => {
+                        println!("Invalid number, please try again.");
+                        continue;
+                    }
+                };
+
+                task_manager.log_time(task_id, hours, minutes);
+            }
+            9 => {
+                println!("Enter task ID to move:");
+                let mut task_id = String::new();
+                io::stdin().read_line(&mut task_id).unwrap();
+                let task_id: u32 = match task_id.trim().parse() {
+                    Ok(num) => num,
+                    Err(_) => {
+                        println!("Invalid ID, please try again.");
+                        continue;
+                    }
+                };
+
+                println!("Enter ID of the task it should come before:");
+                let mut target = String::new();
+                io::stdin().read_line(&mut target).unwrap();
+                let target: u32 = match target.trim().parse() {
+                    Ok(num) => num,
+                    Err(_) => {
+                        println!("Invalid ID, please try again.");
+                        continue;
+                    }
+                };
+
+                task_manager.move_before(task_id, target);
+            }
+            10 => {
+                println!("Enter task ID to move:");
+                let mut task_id = String::new();
+                io::stdin().read_line(&mut task_id).unwrap();
+                let task_id: u32 = match task_id.trim().parse() {
+                    Ok(num) => num,
+                    Err(_) => {
+                        println!("Invalid ID, please try again.");
+                        continue;
+                    }
+                };
+
+                println!("Enter ID of the task it should come after:");
+                let mut target = String::new();
+                io::stdin().read_line(&mut target).unwrap();
+                let target: u32 = match target.trim().parse() {
+                    Ok(num) => num,
+                    Err(_) => {
+                        println!("Invalid ID, please try again.");
+                        continue;
+                    }
+                };
+
+                task_manager.move_after(task_id, target);
+            }
+            11 => {
+                println!("Enter task ID:");
+                let mut task_id = String::new();
+                io::stdin().read_line(&mut task_id).unwrap();
+                let task_id: u32 = match task_id.trim().parse() {
+                    Ok(num) => num,
+                    Err(_) => {
+                        println!("Invalid ID, please try again.");
+                        continue;
+                    }
+                };
+
+                println!("Enter ID of the dependency to remove:");
+                let mut depends_on = String::new();
+                io::stdin().read_line(&mut depends_on).unwrap();
+                let depends_on: u32 = match depends_on.trim().parse() {
+                    Ok(num) => num,
+                    Err(_) => {
+                        println!("Invalid ID, please try again.");
+                        continue;
+                    }
+                };
+
+                task_manager.remove_dependency(task_id, depends_on);
+            }
+            12 => break,
             _ => println!("Invalid choice, please try again."),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_manager(name: &str) -> TaskManager {
+        let path = std::env::temp_dir().join(format!("a-bit-rusty-test-{}-{}.json", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        TaskManager {
+            tasks: HashMap::new(),
+            next_id: 1,
+            path,
+        }
+    }
+
+    fn add_plain_task(manager: &mut TaskManager, title: &str) -> u32 {
+        manager.add_task(title.to_string(), String::new(), None, Priority::Low, HashSet::new());
+        manager.next_id - 1
+    }
+
+    fn add_tagged_task(manager: &mut TaskManager, title: &str, tags: &[&str]) -> u32 {
+        let tags = tags.iter().map(|t| t.to_string()).collect();
+        manager.add_task(title.to_string(), String::new(), None, Priority::Low, tags);
+        manager.next_id - 1
+    }
+
+    #[test]
+    fn cli_parses_add_with_all_options() {
+        let cli = Cli::try_parse_from([
+            "a-bit-rusty",
+            "add",
+            "Write report",
+            "--description",
+            "quarterly",
+            "--due",
+            "2020-01-21T00:00",
+            "--priority",
+            "High",
+            "--tags",
+            "work,urgent",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Some(Command::Add { title, description, due, priority, tags }) => {
+                assert_eq!(title, "Write report");
+                assert_eq!(description, "quarterly");
+                assert_eq!(due.as_deref(), Some("2020-01-21T00:00"));
+                assert_eq!(priority, "High");
+                assert_eq!(tags, "work,urgent");
+            }
+            _ => panic!("expected Command::Add"),
+        }
+    }
+
+    #[test]
+    fn cli_parses_log_and_depend_subcommands() {
+        let cli = Cli::try_parse_from(["a-bit-rusty", "log", "3", "1", "30"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Log { id: 3, hours: 1, minutes: 30 })));
+
+        let cli = Cli::try_parse_from(["a-bit-rusty", "depend", "2", "1"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Depend { id: 2, depends_on: 1 })));
+    }
+
+    #[test]
+    fn cli_with_no_subcommand_runs_interactive_mode() {
+        let cli = Cli::try_parse_from(["a-bit-rusty"]).unwrap();
+        assert!(cli.command.is_none());
+    }
+
+    #[test]
+    fn load_legacy_file_parses_full_pipe_delimited_line() {
+        let mut manager = test_manager("legacy-migration");
+        let legacy_path = std::env::temp_dir().join(format!("a-bit-rusty-test-legacy-{}.txt", std::process::id()));
+        fs::write(
+            &legacy_path,
+            "1|Legacy task|desc|Pending|2020-01-21T00:00:00Z|High|work,urgent|2|2024-01-01:1:30\n",
+        )
+        .unwrap();
+
+        manager.load_legacy_file(&legacy_path);
+        let _ = fs::remove_file(&legacy_path);
+
+        let task = &manager.tasks[&1];
+        assert_eq!(task.title, "Legacy task");
+        assert!(matches!(task.status, TaskStatus::Pending));
+        assert_eq!(task.priority, Priority::High);
+        assert_eq!(task.tags, HashSet::from(["work".to_string(), "urgent".to_string()]));
+        assert_eq!(task.dependencies, HashSet::from([2]));
+        assert_eq!(task.time_entries.len(), 1);
+        assert_eq!((task.time_entries[0].hours, task.time_entries[0].minutes), (1, 30));
+        assert_eq!(manager.next_id, 2);
+    }
+
+    #[test]
+    fn time_entry_new_rolls_excess_minutes_into_hours() {
+        let entry = TimeEntry::new("2024-01-01".to_string(), 1, 90);
+        assert_eq!(entry.hours, 2);
+        assert_eq!(entry.minutes, 30);
+    }
+
+    #[test]
+    fn total_time_sums_logged_entries_across_hour_boundaries() {
+        let mut manager = test_manager("time-totals");
+        let id = add_plain_task(&mut manager, "A");
+
+        manager.log_time(id, 0, 45);
+        manager.log_time(id, 1, 30);
+
+        assert_eq!(manager.tasks[&id].total_time(), (2, 15));
+    }
+
+    #[test]
+    fn tasks_with_tag_filters_to_matching_tasks_only() {
+        let mut manager = test_manager("tag-filter");
+        let urgent = add_tagged_task(&mut manager, "Urgent", &["work", "urgent"]);
+        let _chore = add_tagged_task(&mut manager, "Chore", &["home"]);
+        let also_work = add_tagged_task(&mut manager, "Also work", &["work"]);
+
+        let ids: Vec<u32> = manager.tasks_with_tag("work").iter().map(|task| task.id).collect();
+
+        assert_eq!(ids, vec![urgent, also_work]);
+        assert!(manager.tasks_with_tag("nonexistent").is_empty());
+    }
+
+    #[test]
+    fn parse_due_date_accepts_rfc3339_and_shorthand_forms() {
+        assert!(parse_due_date("2020-01-21T00:00:00Z").is_some());
+        assert!(parse_due_date("2020-01-21T00:00").is_some());
+        assert!(parse_due_date("2020-01-21").is_some());
+        assert!(parse_due_date("not a date").is_none());
+    }
+
+    #[test]
+    fn parse_priority_rejects_unrecognized_input() {
+        assert_eq!(parse_priority("Low"), Some(Priority::Low));
+        assert_eq!(parse_priority("Medium"), Some(Priority::Medium));
+        assert_eq!(parse_priority("High"), Some(Priority::High));
+        assert_eq!(parse_priority("Hgih"), None);
+        assert_eq!(parse_priority(""), None);
+    }
+
+    #[test]
+    fn is_overdue_true_only_for_pending_tasks_past_due() {
+        let past = parse_due_date("2000-01-01").unwrap();
+        let future = parse_due_date("2999-01-01").unwrap();
+
+        let mut overdue = Task::new(1, "A".to_string(), String::new(), Some(past), Priority::Low, HashSet::new(), 0);
+        assert!(overdue.is_overdue());
+
+        overdue.mark_completed();
+        assert!(!overdue.is_overdue());
+
+        let not_due_yet = Task::new(2, "B".to_string(), String::new(), Some(future), Priority::Low, HashSet::new(), 0);
+        assert!(!not_due_yet.is_overdue());
+
+        let no_due_date = Task::new(3, "C".to_string(), String::new(), None, Priority::Low, HashSet::new(), 0);
+        assert!(!no_due_date.is_overdue());
+    }
+
+    #[test]
+    fn add_dependency_rejects_direct_cycle() {
+        let mut manager = test_manager("direct-cycle");
+        let a = add_plain_task(&mut manager, "A");
+        let b = add_plain_task(&mut manager, "B");
+
+        manager.add_dependency(a, b);
+        manager.add_dependency(b, a);
+
+        assert!(manager.tasks[&a].dependencies.contains(&b));
+        assert!(!manager.tasks[&b].dependencies.contains(&a));
+    }
+
+    #[test]
+    fn add_dependency_rejects_transitive_cycle() {
+        let mut manager = test_manager("transitive-cycle");
+        let a = add_plain_task(&mut manager, "A");
+        let b = add_plain_task(&mut manager, "B");
+        let c = add_plain_task(&mut manager, "C");
+
+        manager.add_dependency(a, b);
+        manager.add_dependency(b, c);
+        manager.add_dependency(c, a);
+
+        assert!(manager.tasks[&a].dependencies.contains(&b));
+        assert!(manager.tasks[&b].dependencies.contains(&c));
+        assert!(!manager.tasks[&c].dependencies.contains(&a));
+    }
+
+    #[test]
+    fn mark_completed_blocked_by_pending_dependency() {
+        let mut manager = test_manager("blocked-complete");
+        let a = add_plain_task(&mut manager, "A");
+        let b = add_plain_task(&mut manager, "B");
+        manager.add_dependency(a, b);
+
+        manager.mark_completed(a);
+        assert!(matches!(manager.tasks[&a].status, TaskStatus::Pending));
+
+        manager.mark_completed(b);
+        manager.mark_completed(a);
+        assert!(matches!(manager.tasks[&a].status, TaskStatus::Completed));
+    }
+
+    #[test]
+    fn delete_task_refuses_while_still_a_dependency() {
+        let mut manager = test_manager("delete-referenced");
+        let a = add_plain_task(&mut manager, "A");
+        let b = add_plain_task(&mut manager, "B");
+        manager.add_dependency(a, b);
+
+        manager.delete_task(b);
+        assert!(manager.tasks.contains_key(&b));
+
+        manager.remove_dependency(a, b);
+        manager.delete_task(b);
+        assert!(!manager.tasks.contains_key(&b));
+    }
+
+    #[test]
+    fn remove_dependency_unblocks_completion() {
+        let mut manager = test_manager("remove-dependency");
+        let a = add_plain_task(&mut manager, "A");
+        let b = add_plain_task(&mut manager, "B");
+        manager.add_dependency(a, b);
+
+        manager.mark_completed(a);
+        assert!(matches!(manager.tasks[&a].status, TaskStatus::Pending));
+
+        manager.remove_dependency(a, b);
+        manager.mark_completed(a);
+        assert!(matches!(manager.tasks[&a].status, TaskStatus::Completed));
+    }
+
+    #[test]
+    fn renumber_orders_if_duplicated_breaks_ties_by_id() {
+        let mut manager = test_manager("renumber");
+        for id in [1, 3, 2] {
+            manager.tasks.insert(
+                id,
+                Task::new(id, format!("Task {}", id), String::new(), None, Priority::Low, HashSet::new(), 0),
+            );
+        }
+
+        manager.renumber_orders_if_duplicated();
+
+        assert_eq!(manager.tasks[&1].order, 0);
+        assert_eq!(manager.tasks[&2].order, 1);
+        assert_eq!(manager.tasks[&3].order, 2);
+    }
+
+    #[test]
+    fn renumber_orders_if_duplicated_leaves_distinct_orders_alone() {
+        let mut manager = test_manager("no-renumber");
+        manager.tasks.insert(
+            1,
+            Task::new(1, "Task 1".to_string(), String::new(), None, Priority::Low, HashSet::new(), 5),
+        );
+        manager.tasks.insert(
+            2,
+            Task::new(2, "Task 2".to_string(), String::new(), None, Priority::Low, HashSet::new(), 0),
+        );
+
+        manager.renumber_orders_if_duplicated();
+
+        assert_eq!(manager.tasks[&1].order, 5);
+        assert_eq!(manager.tasks[&2].order, 0);
+    }
+}